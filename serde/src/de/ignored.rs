@@ -0,0 +1,84 @@
+//! A placeholder for fields or elements whose value should be skipped
+//! without caring about its contents.
+
+use std::fmt;
+
+use super::{ContextAccess, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+/// A `Deserialize` implementation that accepts any value and ignores it,
+/// used by derived code to skip map entries whose key doesn't match a known
+/// field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IgnoredAny;
+
+impl<'de> Deserialize<'de> for IgnoredAny {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct IgnoredAnyVisitor;
+
+        impl<'de> Visitor<'de> for IgnoredAnyVisitor {
+            type Value = IgnoredAny;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("anything at all")
+            }
+
+            fn visit_bool<E>(self, _v: bool) -> Result<IgnoredAny, E>
+            where
+                E: super::Error,
+            {
+                Ok(IgnoredAny)
+            }
+
+            fn visit_str<E>(self, _v: &str) -> Result<IgnoredAny, E>
+            where
+                E: super::Error,
+            {
+                Ok(IgnoredAny)
+            }
+
+            fn visit_string<E>(self, _v: String) -> Result<IgnoredAny, E>
+            where
+                E: super::Error,
+            {
+                Ok(IgnoredAny)
+            }
+
+            fn visit_u32<E>(self, _v: u32) -> Result<IgnoredAny, E>
+            where
+                E: super::Error,
+            {
+                Ok(IgnoredAny)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<IgnoredAny, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                while map.next_key::<IgnoredAny>()?.is_some() {
+                    map.next_value::<IgnoredAny>()?;
+                }
+                Ok(IgnoredAny)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<IgnoredAny, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(IgnoredAny)
+            }
+
+            fn visit_context<A>(self, mut context: A) -> Result<IgnoredAny, A::Error>
+            where
+                A: ContextAccess<'de>,
+            {
+                context.inner_value::<IgnoredAny>()
+            }
+        }
+
+        deserializer.deserialize_ignored_any(IgnoredAnyVisitor)
+    }
+}
@@ -0,0 +1,156 @@
+//! Mapping byte offsets in a source input to 1-based line/column positions.
+
+use std::ops::Range;
+
+/// A 1-based line and column position within a source input.
+///
+/// Lines and columns both start counting at 1, matching the convention used
+/// by most editors and compilers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCol {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, counted in `char`s from the start of the line.
+    pub column: usize,
+}
+
+/// The `(line, column)` positions of the start and end of a span, as an
+/// alternative to a raw byte `Range<usize>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColSpan {
+    /// Position of the first byte of the span.
+    pub start: LineCol,
+    /// Position just past the last byte of the span.
+    pub end: LineCol,
+}
+
+/// A precomputed index of newline offsets in a source string, used to map
+/// byte offsets to line/column positions without rescanning the input for
+/// every lookup.
+///
+/// Deserializers that support [`ContextAccess::location`] are expected to
+/// build one `LineIndex` per input and reuse it for every span they report.
+///
+/// [`ContextAccess::location`]: super::ContextAccess::location
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Byte offset of every `\n` in the input, in ascending order.
+    newlines: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Build the index by scanning `input` once for newline offsets.
+    pub fn new(input: &str) -> Self {
+        let newlines = input
+            .char_indices()
+            .filter(|&(_, c)| c == '\n')
+            .map(|(index, _)| index)
+            .collect();
+        LineIndex { newlines }
+    }
+
+    /// Map a single byte offset into `input` to a 1-based line/column
+    /// position.
+    ///
+    /// `offset` must be a valid char boundary in `input` (including
+    /// `input.len()`, one past the final byte).
+    pub fn line_col(&self, input: &str, offset: usize) -> LineCol {
+        // Number of newlines strictly before `offset` gives the 0-based line
+        // index; an offset that falls exactly on a `\n` is still considered
+        // part of the line it terminates, not the next one.
+        let line_index = self.newlines.partition_point(|&nl| nl < offset);
+        let line_start = if line_index == 0 {
+            0
+        } else {
+            self.newlines[line_index - 1] + 1
+        };
+
+        // A CRLF pair counts the trailing `\r` towards the column, same as
+        // every other character: column is simply a `char` count from the
+        // start of the line.
+        let column = input[line_start..offset].chars().count() + 1;
+
+        LineCol {
+            line: line_index + 1,
+            column,
+        }
+    }
+
+    /// Map a byte range into `input` to a `LineColSpan`.
+    pub fn span(&self, input: &str, span: Range<usize>) -> LineColSpan {
+        LineColSpan {
+            start: self.line_col(input, span.start),
+            end: self.line_col(input, span.end),
+        }
+    }
+}
+
+impl LineColSpan {
+    /// Convenience constructor equivalent to building a [`LineIndex`] and
+    /// immediately mapping `span` with it.
+    ///
+    /// Deserializers that report many spans over the same input should build
+    /// a `LineIndex` once instead of calling this repeatedly.
+    pub fn from_byte_range(input: &str, span: Range<usize>) -> Self {
+        LineIndex::new(input).span(input, span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input_is_line_one_column_one() {
+        let index = LineIndex::new("");
+        assert_eq!(
+            index.line_col("", 0),
+            LineCol { line: 1, column: 1 }
+        );
+    }
+
+    #[test]
+    fn offset_on_newline_belongs_to_preceding_line() {
+        let input = "ab\ncd";
+        let index = LineIndex::new(input);
+        assert_eq!(
+            index.line_col(input, 2),
+            LineCol { line: 1, column: 3 }
+        );
+        assert_eq!(
+            index.line_col(input, 3),
+            LineCol { line: 2, column: 1 }
+        );
+    }
+
+    #[test]
+    fn crlf_counts_carriage_return_towards_column() {
+        let input = "ab\r\ncd";
+        let index = LineIndex::new(input);
+        // '\r' is byte 2, '\n' is byte 3, 'c' is byte 4.
+        assert_eq!(
+            index.line_col(input, 4),
+            LineCol { line: 2, column: 1 }
+        );
+        assert_eq!(
+            index.line_col(input, 2),
+            LineCol { line: 1, column: 3 }
+        );
+        // The '\n' itself sits after the '\r', which counts as a column.
+        assert_eq!(
+            index.line_col(input, 3),
+            LineCol { line: 1, column: 4 }
+        );
+    }
+
+    #[test]
+    fn multibyte_utf8_counts_chars_not_bytes() {
+        let input = "héllo";
+        let index = LineIndex::new(input);
+        // 'h' 'é' 'l' 'l' 'o'; 'é' is 2 bytes, so byte offset 3 is 'l'.
+        assert_eq!(
+            index.line_col(input, 3),
+            LineCol { line: 1, column: 3 }
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! Generic data structure deserialization framework.
+//!
+//! Only the subset of the real `serde::de` module needed by the contextful
+//! ("spanned") deserialization feature is reproduced here.
+
+use std::fmt;
+use std::fmt::Display;
+use std::ops::Range;
+
+pub mod value;
+
+pub mod location;
+mod ignored;
+
+pub use self::ignored::IgnoredAny;
+pub use self::location::{LineCol, LineColSpan};
+
+/// The message every default "contextful values are not supported"
+/// implementation in this module raises.
+///
+/// Exposed so that code generated by `#[derive(Deserialize)]` can recognize
+/// the sentinel and fall back to a non-contextful access instead of
+/// propagating the error, without depending on the exact wording.
+pub const CONTEXTFUL_UNSUPPORTED_MESSAGE: &str = "contextful values are not supported";
+
+/// The `Error` trait allows `Deserialize` implementations to create
+/// descriptive error messages belonging to the `Deserializer` they are
+/// deserializing from.
+pub trait Error: Sized + std::error::Error {
+    /// Raised when there is general error when deserializing a type.
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display;
+}
+
+/// A data structure that can be deserialized from any data format supported
+/// by Serde.
+pub trait Deserialize<'de>: Sized {
+    /// Deserialize this value from the given Serde deserializer.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// A `DeserializeSeed` implementation is seeded with extra state that is
+/// passed to `Deserialize::deserialize` in the form of a `Deserializer` and
+/// a `Visitor`. This crate ships with one implementation, `PhantomData<T>`,
+/// for types that implement `Deserialize` without needing extra state.
+pub trait DeserializeSeed<'de>: Sized {
+    /// The type produced by using this seed.
+    type Value;
+
+    /// Equivalent to the more common `Deserialize::deserialize` method, except
+    /// with some initial piece of data (the seed) passed in.
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+impl<'de, T> DeserializeSeed<'de> for std::marker::PhantomData<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// This trait represents a visitor that walks through a deserializer.
+#[allow(unused_variables)]
+pub trait Visitor<'de>: Sized {
+    /// The value produced by this visitor.
+    type Value;
+
+    /// Format a message stating what data this Visitor expects to receive.
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result;
+
+    /// The input contains a boolean.
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Err(Error::custom(format!("invalid type: bool {}", v)))
+    }
+
+    /// The input contains a string.
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Err(Error::custom(format!("invalid type: str {:?}", v)))
+    }
+
+    /// The input contains a string and ownership of the string is being
+    /// given to the `Visitor`.
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visit_str(&v)
+    }
+
+    /// The input contains a `u32`.
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Err(Error::custom(format!("invalid type: u32 {}", v)))
+    }
+
+    /// The input contains a map of key-value pairs.
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let _ = map;
+        Err(Error::custom("invalid type: map"))
+    }
+
+    /// The input contains a sequence of elements.
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let _ = seq;
+        Err(Error::custom("invalid type: sequence"))
+    }
+
+    /// Called when the `Deserializer` has a value alongside its source
+    /// location available, via `Deserializer::deserialize_context`.
+    ///
+    /// The default implementation errors; only visitors that care about
+    /// source spans (such as `Spanned<T>`'s) need to override it.
+    fn visit_context<A>(self, context: A) -> Result<Self::Value, A::Error>
+    where
+        A: ContextAccess<'de>,
+    {
+        let _ = context;
+        Err(Error::custom(CONTEXTFUL_UNSUPPORTED_MESSAGE))
+    }
+}
+
+/// Provides a `Visitor` access to each entry of a map in the input.
+pub trait MapAccess<'de> {
+    /// The error type that can be returned if some error occurs during
+    /// deserialization.
+    type Error: Error;
+
+    /// This returns `Ok(Some(key))` for the next key in the map, or
+    /// `Ok(None)` if there are no more remaining entries.
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>;
+
+    /// This returns a `Ok(value)` for the next value in the map.
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>;
+
+    /// This returns a `Ok(value)` for the next value in the map, handing the
+    /// seed a deserializer that supports `deserialize_context`.
+    ///
+    /// Derived `Deserialize` implementations call this for fields annotated
+    /// `#[serde(spanned)]` (or typed `Spanned<_>`) so that the field can be
+    /// populated with both its value and its span without the whole map
+    /// needing to be deserialized through `Deserializer::deserialize_context`.
+    ///
+    /// The default implementation reports
+    /// [`CONTEXTFUL_UNSUPPORTED_MESSAGE`]; map accesses produced by formats
+    /// that never carry spans can rely on it unmodified, and derived code
+    /// falls back to `next_value_seed` upon seeing that exact message.
+    fn next_value_context_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let _ = &seed;
+        Err(Error::custom(CONTEXTFUL_UNSUPPORTED_MESSAGE))
+    }
+
+    /// This returns the `Deserialize` key for the next key in the map.
+    fn next_key<K>(&mut self) -> Result<Option<K>, Self::Error>
+    where
+        K: Deserialize<'de>,
+    {
+        self.next_key_seed(std::marker::PhantomData)
+    }
+
+    /// This returns the `Deserialize` value for the next value in the map.
+    fn next_value<V>(&mut self) -> Result<V, Self::Error>
+    where
+        V: Deserialize<'de>,
+    {
+        self.next_value_seed(std::marker::PhantomData)
+    }
+}
+
+/// Provides a `Visitor` access to each element of a sequence in the input.
+pub trait SeqAccess<'de> {
+    /// The error type that can be returned if some error occurs during
+    /// deserialization.
+    type Error: Error;
+
+    /// This returns `Ok(Some(value))` for the next value in the sequence,
+    /// or `Ok(None)` if there are no more remaining items.
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>;
+
+    /// This returns `Ok(Some(value))` for the next value in the sequence,
+    /// handing the seed a deserializer that supports `deserialize_context`.
+    ///
+    /// Mirrors `MapAccess::next_value_context_seed`, used by derived code to
+    /// obtain a span per element of a `Vec<Spanned<T>>` field.
+    fn next_element_context_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let _ = &seed;
+        Err(Error::custom(CONTEXTFUL_UNSUPPORTED_MESSAGE))
+    }
+
+    /// This returns `Ok(Some(value))` for the next value in the sequence.
+    fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        self.next_element_seed(std::marker::PhantomData)
+    }
+}
+
+/// Provides a `Visitor` access to the span and underlying value of a
+/// contextful value, as produced by `Deserializer::deserialize_context`.
+///
+/// Analogous to `MapAccess`/`SeqAccess`, but for formats that attach source
+/// location information (byte ranges, and now line/column positions) to the
+/// values they produce.
+pub trait ContextAccess<'de> {
+    /// The error type that can be returned if some error occurs during
+    /// deserialization.
+    type Error: Error;
+
+    /// The byte range in the original input that the current value spans.
+    fn span(&mut self) -> Result<Range<usize>, Self::Error>;
+
+    /// The 1-based line/column range in the original input that the current
+    /// value spans.
+    ///
+    /// Implementations should compute this from `span()` using a
+    /// precomputed index of newline offsets (see [`location::LineIndex`])
+    /// rather than rescanning the whole input for every value.
+    fn location(&mut self) -> Result<LineColSpan, Self::Error>;
+
+    /// Deserializes the inner value, independent of its span.
+    fn inner_value<V>(&mut self) -> Result<V, Self::Error>
+    where
+        V: Deserialize<'de>;
+
+    /// The verbatim source slice covered by the *outer* span, before any
+    /// trimming or other normalization the format performs on its way to
+    /// producing the value itself -- e.g. including the surrounding
+    /// whitespace a format strips before calling `inner_value`.
+    ///
+    /// Implementations that can't borrow a slice for the `'de` lifetime
+    /// (because, for instance, the raw text isn't contiguous in the
+    /// original input) should leave this at its default, which reports that
+    /// raw source text is not supported, and override [`raw_string`]
+    /// instead.
+    ///
+    /// [`raw_string`]: ContextAccess::raw_string
+    fn raw_str(&mut self) -> Result<&'de str, Self::Error> {
+        Err(Error::custom("raw source text is not supported"))
+    }
+
+    /// Like [`raw_str`], but for implementations that can only produce an
+    /// owned copy of the raw source text.
+    ///
+    /// The default implementation forwards to `raw_str`.
+    ///
+    /// [`raw_str`]: ContextAccess::raw_str
+    fn raw_string(&mut self) -> Result<String, Self::Error> {
+        self.raw_str().map(str::to_owned)
+    }
+}
+
+/// A data format that can deserialize any data structure supported by
+/// Serde.
+#[allow(unused_variables)]
+pub trait Deserializer<'de>: Sized {
+    /// The error type that can be returned if some error occurs during
+    /// deserialization.
+    type Error: Error;
+
+    /// Whether this deserializer implements `deserialize_context` itself,
+    /// rather than relying on the default "not supported" error.
+    ///
+    /// `deserialize_context` consumes `self`, so a caller that wants to try
+    /// it and fall back to a different method on failure would need to hold
+    /// on to a second copy of the deserializer, which isn't available in
+    /// general. Checking this constant ahead of time lets `Spanned<T>`'s
+    /// `Deserialize` impl pick the one method to call without requiring
+    /// `D: Clone`.
+    const SUPPORTS_CONTEXT: bool = false;
+
+    /// Require the `Deserializer` to figure out how to drive the visitor
+    /// based on what data type is in the input.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>;
+
+    /// Hint that the `Deserialize` type is expecting a value alongside its
+    /// source location, and would like `deserialize_context` to be driven
+    /// rather than one of the other `deserialize_*` methods.
+    ///
+    /// Deserializers for formats that do not track source spans can rely on
+    /// the default implementation, which reports that contextful values are
+    /// not supported. Such deserializers should leave `SUPPORTS_CONTEXT` at
+    /// its default of `false` so that callers never reach this method in the
+    /// first place.
+    fn deserialize_context<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = &visitor;
+        Err(Error::custom(CONTEXTFUL_UNSUPPORTED_MESSAGE))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str
+        string bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
@@ -0,0 +1,186 @@
+//! Building blocks for deserializing basic values with little or no data
+//! format in between, such as a bare `&str` or `u32`.
+//!
+//! Only the pieces needed by the test suite's contextful-deserialization
+//! tests are reproduced here.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::de::{Deserialize, Deserializer, Visitor};
+
+/// A minimal error type for the `Deserializer` impls in this module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl crate::de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error {
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// A deserializer holding a `&str`.
+#[derive(Debug)]
+pub struct StrDeserializer<'de, E> {
+    value: &'de str,
+    marker: PhantomData<E>,
+}
+
+// Implemented by hand, rather than derived, so that `E` does not need to be
+// `Clone`/`Copy` itself; the marker never actually holds a value of `E`.
+impl<'de, E> Clone for StrDeserializer<'de, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'de, E> Copy for StrDeserializer<'de, E> {}
+
+impl<'de, E> StrDeserializer<'de, E> {
+    /// Create a new deserializer from the given `&str`.
+    pub fn new(value: &'de str) -> Self {
+        StrDeserializer {
+            value,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> Deserializer<'de> for StrDeserializer<'de, E>
+where
+    E: crate::de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.value)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str
+        string bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> Deserialize<'de> for String {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StringVisitor;
+
+        impl<'de> Visitor<'de> for StringVisitor {
+            type Value = String;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<String, E>
+            where
+                E: crate::de::Error,
+            {
+                Ok(v.to_owned())
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<String, E>
+            where
+                E: crate::de::Error,
+            {
+                Ok(v)
+            }
+        }
+
+        deserializer.deserialize_string(StringVisitor)
+    }
+}
+
+/// A deserializer holding a `u32`.
+#[derive(Debug)]
+pub struct U32Deserializer<E> {
+    value: u32,
+    marker: PhantomData<E>,
+}
+
+impl<E> Clone for U32Deserializer<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for U32Deserializer<E> {}
+
+impl<E> U32Deserializer<E> {
+    /// Create a new deserializer from the given `u32`.
+    pub fn new(value: u32) -> Self {
+        U32Deserializer {
+            value,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> Deserializer<'de> for U32Deserializer<E>
+where
+    E: crate::de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u32(self.value)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str
+        string bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> Deserialize<'de> for u32 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct U32Visitor;
+
+        impl<'de> Visitor<'de> for U32Visitor {
+            type Value = u32;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a u32")
+            }
+
+            fn visit_u32<E>(self, v: u32) -> Result<u32, E>
+            where
+                E: crate::de::Error,
+            {
+                Ok(v)
+            }
+        }
+
+        deserializer.deserialize_u32(U32Visitor)
+    }
+}
@@ -0,0 +1,32 @@
+//! # Serde
+//!
+//! Serde is a framework for *ser*ializing and *de*serializing Rust data
+//! structures efficiently and generically.
+//!
+//! This file only contains the small slice of the crate relevant to the
+//! contextful-deserialization ("spanned values") feature; the rest of the
+//! framework is omitted here.
+
+#[macro_use]
+mod macros;
+
+pub mod de;
+mod raw;
+pub mod ser;
+mod spanned;
+
+#[cfg(feature = "derive")]
+pub use serde_derive::Deserialize;
+
+pub use crate::de::{Deserialize, Deserializer};
+pub use crate::raw::Raw;
+pub use crate::ser::{Serialize, Serializer};
+pub use crate::spanned::{
+    Spanned, SPANNED_FIELDS, SPANNED_FIELD_END, SPANNED_FIELD_START, SPANNED_FIELD_VALUE,
+    SPANNED_STRUCT_NAME,
+};
+
+#[doc(hidden)]
+pub mod private {
+    pub use crate::spanned::SpannedVecSeed;
+}
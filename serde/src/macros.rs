@@ -0,0 +1,282 @@
+/// Helper macro used by `forward_to_deserialize_any` below; not meant to be
+/// invoked directly.
+#[macro_export]
+macro_rules! forward_to_deserialize_any_helper {
+    (bool) => {
+        fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (i8) => {
+        fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (i16) => {
+        fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (i32) => {
+        fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (i64) => {
+        fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (i128) => {
+        fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (u8) => {
+        fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (u16) => {
+        fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (u32) => {
+        fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (u64) => {
+        fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (u128) => {
+        fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (f32) => {
+        fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (f64) => {
+        fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (char) => {
+        fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (str) => {
+        fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (string) => {
+        fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (bytes) => {
+        fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (byte_buf) => {
+        fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (option) => {
+        fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (unit) => {
+        fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (unit_struct) => {
+        fn deserialize_unit_struct<V>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (newtype_struct) => {
+        fn deserialize_newtype_struct<V>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (seq) => {
+        fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (tuple) => {
+        fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (tuple_struct) => {
+        fn deserialize_tuple_struct<V>(
+            self,
+            _name: &'static str,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (map) => {
+        fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (struct) => {
+        fn deserialize_struct<V>(
+            self,
+            _name: &'static str,
+            _fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (enum) => {
+        fn deserialize_enum<V>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (identifier) => {
+        fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+    (ignored_any) => {
+        fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: $crate::de::Visitor<'de>,
+        {
+            self.deserialize_any(visitor)
+        }
+    };
+}
+
+/// Implement a `Deserializer` method that always delegates to
+/// `deserialize_any`, for every hint in the given list.
+///
+/// Useful for self-describing formats where all the hint methods are
+/// equivalent, so writing them out by hand would be pure boilerplate.
+#[macro_export]
+macro_rules! forward_to_deserialize_any {
+    ($($ty:ident)*) => {
+        $(
+            $crate::forward_to_deserialize_any_helper!{$ty}
+        )*
+    };
+}
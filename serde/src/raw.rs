@@ -0,0 +1,58 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::de::{ContextAccess, Deserialize, Deserializer, Visitor};
+
+/// A value together with the verbatim source text it was parsed from,
+/// including whatever surrounding trivia (whitespace, quoting, ...) the
+/// format trims away before handing off the value itself.
+///
+/// Unlike `Spanned<T>`, which a deserializer that does not implement
+/// `deserialize_context` can still support through the reserved-name struct
+/// protocol, `Raw<T>` always requires a deserializer
+/// that genuinely supports `deserialize_context` and
+/// `ContextAccess::raw_str`/`raw_string` -- there's no equivalent fallback
+/// protocol for recovering raw source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raw<T> {
+    /// The deserialized value.
+    pub value: T,
+    /// The verbatim source text the value (and its surrounding trivia) was
+    /// parsed from.
+    pub raw: String,
+}
+
+impl<'de, T> Deserialize<'de> for Raw<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for RawVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Raw<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a value with its raw source text")
+            }
+
+            fn visit_context<A>(self, mut context: A) -> Result<Self::Value, A::Error>
+            where
+                A: ContextAccess<'de>,
+            {
+                Ok(Raw {
+                    raw: context.raw_string()?,
+                    value: context.inner_value()?,
+                })
+            }
+        }
+
+        deserializer.deserialize_context(RawVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,60 @@
+//! Generic data structure serialization framework.
+//!
+//! Mirrors `crate::de`, but only the subset needed to round-trip the
+//! contextful ("spanned") values produced by it.
+
+use std::fmt::Display;
+use std::ops::Range;
+
+pub mod value;
+
+/// The `Error` trait allows `Serialize` implementations to create
+/// descriptive error messages belonging to the `Serializer` they are
+/// serializing into.
+pub trait Error: Sized + std::error::Error {
+    /// Raised when there is a general error when serializing a type.
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display;
+}
+
+/// A data structure that can be serialized into any data format supported
+/// by Serde.
+pub trait Serialize {
+    /// Serialize this value into the given Serde serializer.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// A data format that can serialize any data structure supported by Serde.
+pub trait Serializer: Sized {
+    /// The output type produced by this `Serializer` on success.
+    type Ok;
+
+    /// The error type that can be returned if some error occurs during
+    /// serialization.
+    type Error: Error;
+
+    /// Serialize a `&str`.
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error>;
+
+    /// Serialize a `u32`.
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error>;
+
+    /// Hint that the value being serialized carries a source span (as
+    /// produced by `Spanned<T>`), alongside the value itself.
+    ///
+    /// Formats that don't track spans can rely on the default
+    /// implementation, which drops `span` and serializes `value` as though
+    /// the span weren't there -- matching how e.g. TOML's `Spanned`
+    /// serializes only its inner value. Span-aware formats may override this
+    /// to record the range alongside the value.
+    fn serialize_context<T>(self, span: Range<usize>, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let _ = span;
+        value.serialize(self)
+    }
+}
@@ -0,0 +1,60 @@
+//! Building blocks for serializing basic values with little or no data
+//! format in between.
+//!
+//! Only the pieces needed by the test suite's round-trippable-`Spanned`
+//! tests are reproduced here.
+
+use std::fmt;
+
+/// A minimal error type for `Serializer` impls in this module (and in the
+/// test suite).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl crate::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error {
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl crate::ser::Serialize for str {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: crate::ser::Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+impl crate::ser::Serialize for String {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: crate::ser::Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+impl crate::ser::Serialize for u32 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: crate::ser::Serializer,
+    {
+        serializer.serialize_u32(*self)
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut, Range};
+
+use crate::de::{
+    ContextAccess, Deserialize, DeserializeSeed, Deserializer, Error, IgnoredAny, LineColSpan,
+    MapAccess, SeqAccess, Visitor, CONTEXTFUL_UNSUPPORTED_MESSAGE,
+};
+use crate::ser::{Serialize, Serializer};
+
+/// The struct name under which `Spanned<T>` falls back to deserializing
+/// itself when `D::SUPPORTS_CONTEXT` is `false`.
+///
+/// Mirrors the convention used by formats like TOML for values that want to
+/// carry a span without every deserializer needing to implement
+/// `deserialize_context`: a format recognizes this reserved name inside
+/// `deserialize_struct` and, instead of looking for a real struct in the
+/// input, builds a three-field map out of the current value's byte range and
+/// the value itself.
+pub const SPANNED_STRUCT_NAME: &str = "$__private_Spanned";
+
+/// Field name for the start of the byte range, under [`SPANNED_STRUCT_NAME`].
+pub const SPANNED_FIELD_START: &str = "start";
+/// Field name for the end of the byte range, under [`SPANNED_STRUCT_NAME`].
+pub const SPANNED_FIELD_END: &str = "end";
+/// Field name for the wrapped value, under [`SPANNED_STRUCT_NAME`].
+pub const SPANNED_FIELD_VALUE: &str = "value";
+
+/// The fields of [`SPANNED_STRUCT_NAME`], in the order a format should emit
+/// them.
+pub const SPANNED_FIELDS: &[&str] = &[SPANNED_FIELD_START, SPANNED_FIELD_END, SPANNED_FIELD_VALUE];
+
+/// A value together with the byte range and line/column position it was
+/// parsed from.
+///
+/// Obtained either by deserializing `Spanned<T>` directly from a
+/// contextful deserializer, or, inside a struct, by annotating a field
+/// `#[serde(spanned)]` (or simply giving it type `Spanned<T>`, which the
+/// derive macro detects on its own).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    /// The deserialized value.
+    pub inner: T,
+    /// The byte range in the original input the value was parsed from.
+    pub span: Range<usize>,
+    /// The line/column range in the original input the value was parsed
+    /// from, if the deserializer that produced it could supply one.
+    ///
+    /// `None` for values obtained through the [`SPANNED_STRUCT_NAME`]
+    /// byte-offset-only protocol, since there's no line/column position to
+    /// compute it from there.
+    pub location: Option<LineColSpan>,
+}
+
+impl<T> Spanned<T> {
+    /// Constructs a `Spanned` directly from a value and its byte span,
+    /// without going through a deserializer.
+    ///
+    /// `location` is `None`, since there's no line/column position to
+    /// compute it from here.
+    pub fn new(inner: T, span: Range<usize>) -> Self {
+        Spanned {
+            inner,
+            span,
+            location: None,
+        }
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T> PartialEq<&str> for Spanned<T>
+where
+    T: PartialEq<str>,
+{
+    fn eq(&self, other: &&str) -> bool {
+        self.inner.eq(*other)
+    }
+}
+
+impl<T> PartialEq<Spanned<T>> for &str
+where
+    T: PartialEq<str>,
+{
+    fn eq(&self, other: &Spanned<T>) -> bool {
+        other.inner.eq(*self)
+    }
+}
+
+impl<T> Serialize for Spanned<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_context(self.span.clone(), &self.inner)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Spanned<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SpannedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for SpannedVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Spanned<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a spanned value")
+            }
+
+            fn visit_context<A>(self, mut context: A) -> Result<Self::Value, A::Error>
+            where
+                A: ContextAccess<'de>,
+            {
+                Ok(Spanned {
+                    inner: context.inner_value()?,
+                    span: context.span()?,
+                    location: Some(context.location()?),
+                })
+            }
+        }
+
+        // `deserialize_context` consumes `deserializer`, so we have to
+        // commit to a single method ahead of time instead of trying it and
+        // falling back afterwards. Formats that don't support `visit_context`
+        // leave `SUPPORTS_CONTEXT` at its default of `false`; for those we go
+        // straight for the reserved-name struct protocol below, which they
+        // can opt into without ever implementing `deserialize_context`.
+        if D::SUPPORTS_CONTEXT {
+            deserializer.deserialize_context(SpannedVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_struct(
+                SPANNED_STRUCT_NAME,
+                SPANNED_FIELDS,
+                SpannedStructVisitor(PhantomData),
+            )
+        }
+    }
+}
+
+/// The `Visitor` driven by `deserialize_struct(SPANNED_STRUCT_NAME, ...)`,
+/// for formats that support `Spanned<T>` via the reserved-name protocol
+/// instead of `deserialize_context`.
+///
+/// Unlike `SpannedVisitor`, this only has access to the byte range a format
+/// chooses to report, not a line/column position, so `location` comes back
+/// `None`.
+struct SpannedStructVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for SpannedStructVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Spanned<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a `{}` struct", SPANNED_STRUCT_NAME)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut start: Option<u32> = None;
+        let mut end: Option<u32> = None;
+        let mut value: Option<T> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                SPANNED_FIELD_START => start = Some(map.next_value()?),
+                SPANNED_FIELD_END => end = Some(map.next_value()?),
+                SPANNED_FIELD_VALUE => value = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        let start = start.ok_or_else(|| Error::custom("missing `start` field"))?;
+        let end = end.ok_or_else(|| Error::custom("missing `end` field"))?;
+        let value = value.ok_or_else(|| Error::custom("missing `value` field"))?;
+
+        Ok(Spanned {
+            inner: value,
+            span: (start as usize)..(end as usize),
+            location: None,
+        })
+    }
+}
+
+/// A `DeserializeSeed` that deserializes a `Vec<Spanned<T>>` by asking the
+/// `SeqAccess` for the span of each element, rather than deserializing a
+/// plain `Vec<Spanned<T>>` value by value.
+///
+/// Used by derive codegen for fields typed `Vec<Spanned<T>>`; not meant to
+/// be constructed directly by users.
+#[doc(hidden)]
+pub struct SpannedVecSeed<T>(pub PhantomData<T>);
+
+impl<'de, T> DeserializeSeed<'de> for SpannedVecSeed<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Vec<Spanned<T>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SpannedVecVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for SpannedVecVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Vec<Spanned<T>>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a sequence of spanned values")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = Vec::new();
+                loop {
+                    // Mirrors the `next_value_context_seed` fallback used by
+                    // derive codegen for a scalar `Spanned<T>` field (see
+                    // `FieldKind::Spanned` in `serde_derive/src/field.rs`): a
+                    // `SeqAccess` that doesn't support per-element spans
+                    // reports `CONTEXTFUL_UNSUPPORTED_MESSAGE`, and we fall
+                    // back to plain element deserialization, which can still
+                    // produce a `Spanned<T>` via the reserved-name struct
+                    // protocol.
+                    let next = match seq.next_element_context_seed(PhantomData::<Spanned<T>>) {
+                        Ok(next) => next,
+                        Err(error) if error.to_string() == CONTEXTFUL_UNSUPPORTED_MESSAGE => {
+                            seq.next_element_seed(PhantomData::<Spanned<T>>)?
+                        }
+                        Err(error) => return Err(error),
+                    };
+                    match next {
+                        Some(value) => values.push(value),
+                        None => break,
+                    }
+                }
+                Ok(values)
+            }
+        }
+
+        deserializer.deserialize_seq(SpannedVecVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Field, GenericArgument, Ident, PathArguments, Type};
+
+/// How a field's value should be pulled out of the `MapAccess`.
+pub enum FieldKind {
+    /// An ordinary field, deserialized with `next_value_seed`.
+    Plain,
+    /// A field typed `Spanned<T>` (or annotated `#[serde(spanned)]`),
+    /// deserialized with `next_value_context_seed`, falling back to
+    /// `next_value_seed` when the format doesn't support it.
+    Spanned,
+    /// A field typed `Vec<Spanned<T>>`, deserialized element-by-element via
+    /// `SpannedVecSeed` so each element keeps its own span.
+    SpannedVec { inner: Box<Type> },
+}
+
+pub struct ParsedField {
+    pub ident: Ident,
+    pub ty: Type,
+    pub kind: FieldKind,
+}
+
+impl ParsedField {
+    pub fn parse(field: &Field) -> syn::Result<Self> {
+        let ident = field
+            .ident
+            .clone()
+            .expect("named fields always have an ident");
+        let ty = field.ty.clone();
+
+        let explicit_spanned = has_spanned_attr(field)?;
+
+        // The explicit attribute is trusted outright, for types that wrap
+        // `Spanned<T>` under an alias the syntactic check below can't see
+        // through. Absent the attribute, a field literally typed
+        // `Spanned<T>` is detected on its own.
+        let kind = if let Some(inner) = vec_of_spanned_inner(&ty) {
+            FieldKind::SpannedVec {
+                inner: Box::new(inner),
+            }
+        } else if explicit_spanned || spanned_inner(&ty).is_some() {
+            FieldKind::Spanned
+        } else {
+            FieldKind::Plain
+        };
+
+        Ok(ParsedField { ident, ty, kind })
+    }
+
+    /// The expression (of type `#ty`) used to pull this field's value out of
+    /// `map`, assuming `map: impl MapAccess<'de>` is in scope.
+    pub fn value_expr(&self) -> TokenStream {
+        let ty = &self.ty;
+        match &self.kind {
+            FieldKind::Plain => quote! {
+                ::serde::de::MapAccess::next_value_seed(
+                    &mut map,
+                    ::std::marker::PhantomData::<#ty>,
+                )?
+            },
+            FieldKind::Spanned => quote! {
+                match ::serde::de::MapAccess::next_value_context_seed(
+                    &mut map,
+                    ::std::marker::PhantomData::<#ty>,
+                ) {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(error)
+                        if error.to_string() == ::serde::de::CONTEXTFUL_UNSUPPORTED_MESSAGE =>
+                    {
+                        ::serde::de::MapAccess::next_value_seed(
+                            &mut map,
+                            ::std::marker::PhantomData::<#ty>,
+                        )?
+                    }
+                    ::std::result::Result::Err(error) => return ::std::result::Result::Err(error),
+                }
+            },
+            FieldKind::SpannedVec { inner } => quote! {
+                ::serde::de::MapAccess::next_value_seed(
+                    &mut map,
+                    ::serde::private::SpannedVecSeed::<#inner>(::std::marker::PhantomData),
+                )?
+            },
+        }
+    }
+}
+
+fn has_spanned_attr(field: &Field) -> syn::Result<bool> {
+    let mut spanned = false;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("spanned") {
+                spanned = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported serde attribute"))
+            }
+        })?;
+    }
+    Ok(spanned)
+}
+
+/// If `ty` is syntactically `Spanned<T>`, returns `T`.
+fn spanned_inner(ty: &Type) -> Option<&Type> {
+    single_generic_arg(ty, "Spanned")
+}
+
+/// If `ty` is syntactically `Vec<Spanned<T>>`, returns `T`.
+fn vec_of_spanned_inner(ty: &Type) -> Option<Type> {
+    let vec_arg = single_generic_arg(ty, "Vec")?;
+    spanned_inner(vec_arg).cloned()
+}
+
+fn single_generic_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    if args.args.len() != 1 {
+        return None;
+    }
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
@@ -0,0 +1,179 @@
+//! Macros 1.1 implementation of `#[derive(Deserialize)]`.
+//!
+//! Only named-field structs are supported so far; this is a narrow
+//! reimplementation focused on threading source-location context through to
+//! fields typed `Spanned<T>` or `Vec<Spanned<T>>` (see `#[serde(spanned)]`).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+mod field;
+
+use field::ParsedField;
+
+#[proc_macro_derive(Deserialize, attributes(serde))]
+pub fn derive_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_derive_deserialize(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_derive_deserialize(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "#[derive(Deserialize)] only supports structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "#[derive(Deserialize)] only supports structs with named fields",
+            ))
+        }
+    };
+
+    let parsed_fields = fields
+        .iter()
+        .map(ParsedField::parse)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let field_idents: Vec<_> = parsed_fields.iter().map(|f| f.ident.clone()).collect();
+    let field_names: Vec<_> = parsed_fields
+        .iter()
+        .map(|f| f.ident.to_string())
+        .collect();
+    let field_types: Vec<Type> = parsed_fields.iter().map(|f| f.ty.clone()).collect();
+
+    let field_enum_variants = field_idents.clone();
+
+    let key_match_arms = field_idents.iter().zip(field_names.iter()).map(|(id, name)| {
+        quote! { #name => ::std::result::Result::Ok(__Field::#id) }
+    });
+
+    let value_exprs = parsed_fields.iter().map(|f| f.value_expr());
+
+    let missing_field_errors = field_idents.iter().zip(field_names.iter()).map(|(id, name)| {
+        quote! {
+            let #id = #id.ok_or_else(|| {
+                <A::Error as ::serde::de::Error>::custom(concat!("missing field `", #name, "`"))
+            })?;
+        }
+    });
+
+    let duplicate_field_checks = field_idents.iter().zip(field_names.iter()).map(|(id, name)| {
+        quote! {
+            if #id.is_some() {
+                return ::std::result::Result::Err(<A::Error as ::serde::de::Error>::custom(
+                    concat!("duplicate field `", #name, "`"),
+                ));
+            }
+        }
+    });
+
+    let expecting = format!("struct {}", ident);
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<'de> ::serde::de::Deserialize<'de> for #ident {
+            fn deserialize<__D>(deserializer: __D) -> ::std::result::Result<Self, __D::Error>
+            where
+                __D: ::serde::de::Deserializer<'de>,
+            {
+                #[allow(non_camel_case_types)]
+                enum __Field {
+                    #(#field_enum_variants,)*
+                    __ignore,
+                }
+
+                impl<'de> ::serde::de::Deserialize<'de> for __Field {
+                    fn deserialize<__D>(deserializer: __D) -> ::std::result::Result<Self, __D::Error>
+                    where
+                        __D: ::serde::de::Deserializer<'de>,
+                    {
+                        struct __FieldVisitor;
+
+                        impl<'de> ::serde::de::Visitor<'de> for __FieldVisitor {
+                            type Value = __Field;
+
+                            fn expecting(
+                                &self,
+                                formatter: &mut ::std::fmt::Formatter,
+                            ) -> ::std::fmt::Result {
+                                formatter.write_str("field identifier")
+                            }
+
+                            fn visit_str<__E>(
+                                self,
+                                value: &str,
+                            ) -> ::std::result::Result<__Field, __E>
+                            where
+                                __E: ::serde::de::Error,
+                            {
+                                match value {
+                                    #(#key_match_arms,)*
+                                    _ => ::std::result::Result::Ok(__Field::__ignore),
+                                }
+                            }
+                        }
+
+                        deserializer.deserialize_identifier(__FieldVisitor)
+                    }
+                }
+
+                struct __Visitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for __Visitor {
+                    type Value = #ident;
+
+                    fn expecting(
+                        &self,
+                        formatter: &mut ::std::fmt::Formatter,
+                    ) -> ::std::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_map<A>(self, mut map: A) -> ::std::result::Result<Self::Value, A::Error>
+                    where
+                        A: ::serde::de::MapAccess<'de>,
+                    {
+                        #(let mut #field_idents: ::std::option::Option<#field_types> = ::std::option::Option::None;)*
+
+                        while let ::std::option::Option::Some(key) = map.next_key::<__Field>()? {
+                            match key {
+                                #(
+                                    __Field::#field_idents => {
+                                        #duplicate_field_checks
+                                        #field_idents = ::std::option::Option::Some(#value_exprs);
+                                    }
+                                )*
+                                __Field::__ignore => {
+                                    map.next_value::<::serde::de::IgnoredAny>()?;
+                                }
+                            }
+                        }
+
+                        #(#missing_field_errors)*
+
+                        ::std::result::Result::Ok(#ident {
+                            #(#field_idents,)*
+                        })
+                    }
+                }
+
+                const FIELDS: &[&str] = &[#(#field_names,)*];
+                deserializer.deserialize_struct(stringify!(#ident), FIELDS, __Visitor)
+            }
+        }
+    })
+}
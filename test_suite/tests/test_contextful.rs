@@ -1,18 +1,32 @@
-use std::{marker::PhantomData, ops::Range};
+use std::ops::Range;
 
 use serde::{
     de::{
         value::{Error, StrDeserializer, U32Deserializer},
-        ContextAccess, Deserialize, Deserializer, Error as _, Visitor,
+        ContextAccess, Deserialize, Deserializer, Error as _, LineColSpan, Visitor,
     },
-    forward_to_deserialize_any,
+    forward_to_deserialize_any, Raw, Spanned,
 };
+use serde::de::location::LineIndex;
 
-struct TrimDeserializer(String);
+struct TrimDeserializer {
+    input: String,
+    lines: LineIndex,
+}
+
+impl TrimDeserializer {
+    fn new(input: impl Into<String>) -> Self {
+        let input = input.into();
+        let lines = LineIndex::new(&input);
+        TrimDeserializer { input, lines }
+    }
+}
 
 impl<'de> Deserializer<'de> for &'de mut TrimDeserializer {
     type Error = Error;
 
+    const SUPPORTS_CONTEXT: bool = true;
+
     forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str
                                 string bytes byte_buf option unit unit_struct newtype_struct seq
                                 tuple tuple_struct map struct enum identifier ignored_any);
@@ -29,21 +43,24 @@ impl<'de> Deserializer<'de> for &'de mut TrimDeserializer {
         V: Visitor<'de>,
     {
         let start_index = self
-            .0
+            .input
             .char_indices()
             .find(|&(_, c)| !c.is_whitespace())
             .map(|(index, _)| index)
             .ok_or(Error::custom("no spaces found"))?;
         let end_index = self
-            .0
+            .input
             .char_indices()
             .rev()
             .find(|&(_, c)| !c.is_whitespace())
             .map(|(index, c)| index + c.len_utf8())
             .ok_or(Error::custom("no spaces found"))?;
         visitor.visit_context(ContextfulTrimAccess {
-            de: StrDeserializer::new(self.0.get(start_index..end_index).unwrap()),
+            de: StrDeserializer::new(self.input.get(start_index..end_index).unwrap()),
             span: start_index..end_index,
+            full_span: 0..self.input.len(),
+            input: &self.input,
+            lines: &self.lines,
         })
     }
 }
@@ -52,6 +69,11 @@ impl<'de> Deserializer<'de> for &'de mut TrimDeserializer {
 struct ContextfulTrimAccess<'de> {
     de: StrDeserializer<'de, Error>,
     span: Range<usize>,
+    /// The whole original input, before the surrounding whitespace was
+    /// trimmed off to find `span`.
+    full_span: Range<usize>,
+    input: &'de str,
+    lines: &'de LineIndex,
 }
 
 impl<'de> ContextAccess<'de> for ContextfulTrimAccess<'de> {
@@ -61,70 +83,76 @@ impl<'de> ContextAccess<'de> for ContextfulTrimAccess<'de> {
         Ok(self.span.clone())
     }
 
+    fn location(&mut self) -> Result<LineColSpan, Self::Error> {
+        Ok(self.lines.span(self.input, self.span.clone()))
+    }
+
     fn inner_value<V>(&mut self) -> Result<V, Self::Error>
     where
         V: Deserialize<'de>,
     {
         V::deserialize(self.de)
     }
-}
 
-#[derive(Debug)]
-struct Spanned<T> {
-    inner: T,
-    span: Range<usize>,
+    fn raw_str(&mut self) -> Result<&'de str, Self::Error> {
+        Ok(self.input.get(self.full_span.clone()).unwrap())
+    }
 }
 
-impl<'de, T> Deserialize<'de> for Spanned<T>
-where
-    T: Deserialize<'de>,
-{
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct SpannedVisitor<T>(PhantomData<T>);
-
-        impl<'de, T> Visitor<'de> for SpannedVisitor<T>
-        where
-            T: Deserialize<'de>,
-        {
-            type Value = Spanned<T>;
-
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                write!(formatter, "a spanned value")
-            }
-
-            fn visit_context<A>(self, mut context: A) -> Result<Self::Value, A::Error>
-            where
-                A: ContextAccess<'de>,
-            {
-                Ok(Spanned {
-                    inner: context.inner_value()?,
-                    span: context.span()?,
-                })
-            }
-        }
-
-        deserializer.deserialize_context(SpannedVisitor(PhantomData))
-    }
+#[test]
+fn test_raw_keeps_surrounding_whitespace() {
+    let mut de = TrimDeserializer::new("   test  ");
+    let raw: Raw<String> = Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!(raw.value, "test");
+    assert_eq!(raw.raw, "   test  ");
 }
 
 #[test]
 fn test_spanned() {
-    let mut de = TrimDeserializer("   test  ".to_string());
+    let mut de = TrimDeserializer::new("   test  ");
     let spanned: Spanned<String> = Deserialize::deserialize(&mut de).unwrap();
     assert_eq!(spanned.inner, "test");
     assert_eq!(spanned.span, 3..7);
 }
 
+#[test]
+fn test_spanned_location_single_line() {
+    let mut de = TrimDeserializer::new("   test  ");
+    let spanned: Spanned<String> = Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!(
+        spanned.location,
+        Some(LineColSpan {
+            start: serde::de::LineCol { line: 1, column: 4 },
+            end: serde::de::LineCol { line: 1, column: 8 },
+        })
+    );
+}
+
+#[test]
+fn test_spanned_location_across_lines() {
+    let mut de = TrimDeserializer::new("\n  test\n");
+    let spanned: Spanned<String> = Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!(
+        spanned.location,
+        Some(LineColSpan {
+            start: serde::de::LineCol { line: 2, column: 3 },
+            end: serde::de::LineCol { line: 2, column: 7 },
+        })
+    );
+}
+
 #[test]
 fn test_unsupported_spanned() {
+    // `U32Deserializer` leaves `SUPPORTS_CONTEXT` at its default of `false`,
+    // so `Spanned::deserialize` goes straight for the reserved-name struct
+    // protocol instead of `deserialize_context`. `U32Deserializer` doesn't
+    // recognize that struct name either, so it falls back to its ordinary
+    // `deserialize_any`, which hands the visitor a `u32` it can't use.
     let deserializer = U32Deserializer::<Error>::new(42);
     match Spanned::<u32>::deserialize(deserializer) {
         Ok(v) => panic!("unexpected value: {:?}", v),
         Err(e) => {
-            assert_eq!(e, Error::custom("contextful values are not supported"));
+            assert_eq!(e, Error::custom("invalid type: u32 42"));
         }
     }
 }
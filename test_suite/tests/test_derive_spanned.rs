@@ -0,0 +1,335 @@
+//! Exercises `#[derive(Deserialize)]` support for `#[serde(spanned)]`
+//! fields, backed by a toy "key = value" map format whose `MapAccess` and
+//! `SeqAccess` both support handing out spans for their entries.
+
+use std::ops::Range;
+
+use serde::{
+    de::{
+        value::{Error, StrDeserializer},
+        ContextAccess, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor,
+    },
+    Deserialize, Spanned,
+};
+
+/// One `key = value` pair, with the byte range of the value (not counting
+/// surrounding whitespace).
+struct Entry<'de> {
+    key: &'de str,
+    value: &'de str,
+    value_span: Range<usize>,
+}
+
+/// A deserializer for a flat sequence of `key = value` lines, each value
+/// being either a bare word or a `[a, b, c]` list of bare words.
+struct EntryMapDeserializer<'de> {
+    input: &'de str,
+    entries: Vec<Entry<'de>>,
+}
+
+impl<'de> EntryMapDeserializer<'de> {
+    fn parse(input: &'de str) -> Self {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        for line in input.split('\n') {
+            let line_offset = offset;
+            offset += line.len() + 1;
+            let Some((key, rest)) = line.split_once('=') else {
+                continue;
+            };
+            let value_start_in_line = line.len() - rest.len() + rest.len() - rest.trim_start().len();
+            let trimmed = rest.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let value_start = line_offset + value_start_in_line;
+            entries.push(Entry {
+                key: key.trim(),
+                value: trimmed,
+                value_span: value_start..(value_start + trimmed.len()),
+            });
+        }
+        EntryMapDeserializer { input, entries }
+    }
+}
+
+impl<'de> Deserializer<'de> for &'de EntryMapDeserializer<'de> {
+    type Error = Error;
+
+    serde::forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        str string bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        map enum identifier ignored_any);
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_struct("", &[], visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(EntryMap {
+            input: self.input,
+            entries: self.entries.iter(),
+        })
+    }
+}
+
+struct EntryMap<'de, 'a> {
+    input: &'de str,
+    entries: std::slice::Iter<'a, Entry<'de>>,
+}
+
+struct ValueDeserializer<'de> {
+    input: &'de str,
+    value: &'de str,
+    span: Range<usize>,
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    const SUPPORTS_CONTEXT: bool = true;
+
+    serde::forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        str string bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        map struct enum identifier ignored_any);
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let Some(list) = self.value.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            visitor.visit_seq(WordList {
+                input: self.input,
+                base: self.span.start + 1,
+                rest: list,
+                offset_in_list: 0,
+            })
+        } else {
+            visitor.visit_str(self.value)
+        }
+    }
+
+    fn deserialize_context<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_context(ValueContext {
+            de: StrDeserializer::new(self.value),
+            input: self.input,
+            span: self.span,
+        })
+    }
+}
+
+struct ValueContext<'de> {
+    de: StrDeserializer<'de, Error>,
+    input: &'de str,
+    span: Range<usize>,
+}
+
+impl<'de> ContextAccess<'de> for ValueContext<'de> {
+    type Error = Error;
+
+    fn span(&mut self) -> Result<Range<usize>, Self::Error> {
+        Ok(self.span.clone())
+    }
+
+    fn location(&mut self) -> Result<serde::de::LineColSpan, Self::Error> {
+        Ok(serde::de::LineColSpan::from_byte_range(self.input, self.span.clone()))
+    }
+
+    fn inner_value<V>(&mut self) -> Result<V, Self::Error>
+    where
+        V: serde::Deserialize<'de>,
+    {
+        V::deserialize(self.de)
+    }
+}
+
+/// A `[a, b, c]` list of bare words, each tracked with its own span within
+/// the original input.
+struct WordList<'de> {
+    input: &'de str,
+    base: usize,
+    rest: &'de str,
+    offset_in_list: usize,
+}
+
+impl<'de> SeqAccess<'de> for WordList<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.next_word()? {
+            Some((word, _span)) => seed.deserialize(StrDeserializer::new(word)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_element_context_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.next_word()? {
+            Some((word, span)) => seed
+                .deserialize(ValueDeserializer {
+                    input: self.input,
+                    value: word,
+                    span,
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'de> WordList<'de> {
+    fn next_word(&mut self) -> Result<Option<(&'de str, Range<usize>)>, Error> {
+        loop {
+            let remaining = &self.rest[self.offset_in_list..];
+            let remaining = remaining.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+            let skipped = self.rest.len() - self.offset_in_list - remaining.len();
+            self.offset_in_list += skipped;
+            if remaining.is_empty() {
+                return Ok(None);
+            }
+            let end = remaining.find(',').unwrap_or(remaining.len());
+            let word = remaining[..end].trim_end();
+            let start_in_list = self.offset_in_list;
+            self.offset_in_list += end;
+            if word.is_empty() {
+                continue;
+            }
+            let start = self.base + start_in_list;
+            return Ok(Some((word, start..(start + word.len()))));
+        }
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for EntryMap<'de, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.clone().next() {
+            Some(entry) => seed
+                .deserialize(StrDeserializer::new(entry.key))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let entry = self.entries.next().expect("next_key_seed was just called");
+        seed.deserialize(ValueDeserializer {
+            input: self.input,
+            value: entry.value,
+            span: entry.value_span.clone(),
+        })
+    }
+
+    fn next_value_context_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let entry = self.entries.next().expect("next_key_seed was just called");
+        let deserializer = ValueDeserializer {
+            input: self.input,
+            value: entry.value,
+            span: entry.value_span.clone(),
+        };
+        deserializer.deserialize_context(ContextSeedVisitor(seed))
+    }
+}
+
+/// Adapts a `DeserializeSeed` into the `Visitor` that `deserialize_context`
+/// expects, by forwarding straight to `seed.deserialize` with a deserializer
+/// that always routes through `visit_context`.
+struct ContextSeedVisitor<V>(V);
+
+impl<'de, V> Visitor<'de> for ContextSeedVisitor<V>
+where
+    V: DeserializeSeed<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a contextful value")
+    }
+
+    fn visit_context<A>(self, context: A) -> Result<Self::Value, A::Error>
+    where
+        A: ContextAccess<'de>,
+    {
+        self.0.deserialize(ContextOnlyDeserializer(context))
+    }
+}
+
+/// A `Deserializer` whose only supported hint is `deserialize_context`,
+/// forwarding straight to the wrapped `ContextAccess`.
+struct ContextOnlyDeserializer<A>(A);
+
+impl<'de, A> Deserializer<'de> for ContextOnlyDeserializer<A>
+where
+    A: ContextAccess<'de>,
+{
+    type Error = A::Error;
+
+    const SUPPORTS_CONTEXT: bool = true;
+
+    serde::forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        str string bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        map struct enum identifier ignored_any);
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_context(visitor)
+    }
+
+    fn deserialize_context<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_context(self.0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    name: Spanned<String>,
+    tags: Vec<Spanned<String>>,
+}
+
+#[test]
+fn test_spanned_field_nested_in_struct() {
+    let de = EntryMapDeserializer::parse("name = widget\ntags = [a, bb]");
+    let config: Config = Deserialize::deserialize(&de).unwrap();
+
+    assert_eq!(config.name.inner, "widget");
+    assert_eq!(config.name.span, 7..13);
+
+    assert_eq!(config.tags.len(), 2);
+    assert_eq!(config.tags[0].inner, "a");
+    assert_eq!(config.tags[1].inner, "bb");
+    assert_eq!(&"name = widget\ntags = [a, bb]"[config.tags[0].span.clone()], "a");
+    assert_eq!(&"name = widget\ntags = [a, bb]"[config.tags[1].span.clone()], "bb");
+}
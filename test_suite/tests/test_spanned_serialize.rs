@@ -0,0 +1,92 @@
+//! Exercises `Spanned<T>: Serialize`: by default a span-unaware format
+//! serializes only the inner value, while a span-aware format can opt into
+//! recording the range via `Serializer::serialize_context`.
+
+use serde::ser::value::Error;
+use serde::{Serialize, Serializer, Spanned};
+
+/// A serializer that only ever produces the serialized value as a `String`,
+/// ignoring any span it's told about. Represents an ordinary, span-unaware
+/// format.
+struct PlainSerializer;
+
+impl Serializer for PlainSerializer {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+}
+
+/// A serializer that records the span it's given alongside the serialized
+/// value. Represents a span-aware format.
+struct SpanRecordingSerializer {
+    span: Option<std::ops::Range<usize>>,
+}
+
+struct Recorded {
+    value: String,
+    span: Option<std::ops::Range<usize>>,
+}
+
+impl Serializer for SpanRecordingSerializer {
+    type Ok = Recorded;
+    type Error = Error;
+
+    fn serialize_str(self, v: &str) -> Result<Recorded, Error> {
+        Ok(Recorded {
+            value: v.to_owned(),
+            span: self.span,
+        })
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Recorded, Error> {
+        Ok(Recorded {
+            value: v.to_string(),
+            span: self.span,
+        })
+    }
+
+    fn serialize_context<T>(
+        mut self,
+        span: std::ops::Range<usize>,
+        value: &T,
+    ) -> Result<Recorded, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.span = Some(span);
+        value.serialize(self)
+    }
+}
+
+#[test]
+fn test_spanned_serializes_to_inner_by_default() {
+    let spanned = Spanned::new("widget".to_owned(), 7..13);
+    let out = spanned.serialize(PlainSerializer).unwrap();
+    assert_eq!(out, "widget");
+}
+
+#[test]
+fn test_spanned_serializes_with_span_when_supported() {
+    let spanned = Spanned::new("widget".to_owned(), 7..13);
+    let out = spanned
+        .serialize(SpanRecordingSerializer { span: None })
+        .unwrap();
+    assert_eq!(out.value, "widget");
+    assert_eq!(out.span, Some(7..13));
+}
+
+#[test]
+fn test_spanned_new_and_mutation() {
+    let mut spanned = Spanned::new("widget".to_owned(), 7..13);
+    assert_eq!(spanned, "widget");
+    spanned.push('!');
+    assert_eq!(*spanned, "widget!".to_owned());
+    assert_eq!(spanned, "widget!");
+}
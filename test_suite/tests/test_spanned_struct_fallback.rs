@@ -0,0 +1,125 @@
+//! Exercises the reserved-name struct protocol that `Spanned<T>` falls back
+//! to for formats that never implement `deserialize_context` at all, only
+//! `deserialize_struct`. Mirrors how a real self-describing format (e.g.
+//! TOML) recognizes `SPANNED_STRUCT_NAME` and answers with a three-field map
+//! built from the value's byte range, rather than a struct actually present
+//! in the input.
+
+use serde::de::value::{Error, U32Deserializer};
+use serde::de::{
+    value::StrDeserializer, DeserializeSeed, Deserializer, Error as _, MapAccess, Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserialize, Spanned};
+
+/// A deserializer for values encoded as `"<start>:<end>:<value>"`, e.g.
+/// `"3:7:test"`. Supports `Spanned<T>` purely through
+/// `deserialize_struct(SPANNED_STRUCT_NAME, ..)`; it has no notion of
+/// `deserialize_context` at all.
+struct ReservedProtocolDeserializer<'de>(&'de str);
+
+impl<'de> Deserializer<'de> for ReservedProtocolDeserializer<'de> {
+    type Error = Error;
+
+    forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str
+        string bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+        enum identifier ignored_any);
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name != serde::SPANNED_STRUCT_NAME {
+            return Err(Error::custom(format!("unexpected struct `{}`", name)));
+        }
+        let mut parts = self.0.splitn(3, ':');
+        let start: u32 = parts
+            .next()
+            .ok_or_else(|| Error::custom("missing start"))?
+            .parse()
+            .map_err(Error::custom)?;
+        let end: u32 = parts
+            .next()
+            .ok_or_else(|| Error::custom("missing end"))?
+            .parse()
+            .map_err(Error::custom)?;
+        let value = parts.next().ok_or_else(|| Error::custom("missing value"))?;
+        visitor.visit_map(ReservedFieldMap {
+            start,
+            end,
+            value,
+            next: 0,
+        })
+    }
+}
+
+/// Hands out the `start`, `end`, and `value` entries in order, as
+/// `deserialize_struct` above expects `Spanned<T>`'s `SpannedStructVisitor`
+/// to ask for them.
+struct ReservedFieldMap<'de> {
+    start: u32,
+    end: u32,
+    value: &'de str,
+    next: u8,
+}
+
+impl<'de> MapAccess<'de> for ReservedFieldMap<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match self.next {
+            0 => serde::SPANNED_FIELD_START,
+            1 => serde::SPANNED_FIELD_END,
+            2 => serde::SPANNED_FIELD_VALUE,
+            _ => return Ok(None),
+        };
+        seed.deserialize(StrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let field = self.next;
+        self.next += 1;
+        match field {
+            0 => seed.deserialize(U32Deserializer::new(self.start)),
+            1 => seed.deserialize(U32Deserializer::new(self.end)),
+            2 => seed.deserialize(StrDeserializer::new(self.value)),
+            _ => panic!("next_value_seed called without a matching next_key_seed"),
+        }
+    }
+}
+
+#[test]
+fn test_spanned_via_reserved_struct_protocol() {
+    let de = ReservedProtocolDeserializer("3:7:test");
+    let spanned: Spanned<String> = Deserialize::deserialize(de).unwrap();
+    assert_eq!(spanned.inner, "test");
+    assert_eq!(spanned.span, 3..7);
+}
+
+#[test]
+fn test_spanned_new_equals_reserved_struct_protocol_value() {
+    // Both paths leave `location` as `None`, so a `Spanned` built with
+    // `Spanned::new` for re-serialization compares equal to an
+    // otherwise-identical one obtained by deserializing through the
+    // reserved-name struct protocol.
+    let de = ReservedProtocolDeserializer("3:7:test");
+    let spanned: Spanned<String> = Deserialize::deserialize(de).unwrap();
+    assert_eq!(spanned, Spanned::new("test".to_owned(), 3..7));
+}
@@ -0,0 +1,165 @@
+//! Exercises the `SeqAccess::next_element_context_seed` fallback in
+//! `SpannedVecSeed` (used by derive codegen for `Vec<Spanned<T>>` fields):
+//! a `SeqAccess` that doesn't support per-element spans should still produce
+//! `Spanned<T>` values, each falling back further to the reserved-name
+//! struct protocol for their own span.
+
+use serde::de::value::{Error, StrDeserializer, U32Deserializer};
+use serde::de::{DeserializeSeed, Deserializer, Error as _, MapAccess, SeqAccess, Visitor};
+use serde::private::SpannedVecSeed;
+use serde::Spanned;
+
+/// Elements encoded as `"<start>:<end>:<value>"`. Implements only the
+/// reserved-name struct protocol, like `ReservedProtocolDeserializer` in
+/// `test_spanned_struct_fallback.rs`, and never overrides
+/// `SUPPORTS_CONTEXT`.
+struct ReservedProtocolElement<'de>(&'de str);
+
+impl<'de> Deserializer<'de> for ReservedProtocolElement<'de> {
+    type Error = Error;
+
+    serde::forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        str string bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct
+        map enum identifier ignored_any);
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name != serde::SPANNED_STRUCT_NAME {
+            return Err(Error::custom(format!("unexpected struct `{}`", name)));
+        }
+        let mut parts = self.0.splitn(3, ':');
+        let start: u32 = parts
+            .next()
+            .ok_or_else(|| Error::custom("missing start"))?
+            .parse()
+            .map_err(Error::custom)?;
+        let end: u32 = parts
+            .next()
+            .ok_or_else(|| Error::custom("missing end"))?
+            .parse()
+            .map_err(Error::custom)?;
+        let value = parts.next().ok_or_else(|| Error::custom("missing value"))?;
+        visitor.visit_map(ReservedFieldMap {
+            start,
+            end,
+            value,
+            next: 0,
+        })
+    }
+}
+
+struct ReservedFieldMap<'de> {
+    start: u32,
+    end: u32,
+    value: &'de str,
+    next: u8,
+}
+
+impl<'de> MapAccess<'de> for ReservedFieldMap<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match self.next {
+            0 => serde::SPANNED_FIELD_START,
+            1 => serde::SPANNED_FIELD_END,
+            2 => serde::SPANNED_FIELD_VALUE,
+            _ => return Ok(None),
+        };
+        seed.deserialize(StrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let field = self.next;
+        self.next += 1;
+        match field {
+            0 => seed.deserialize(U32Deserializer::new(self.start)),
+            1 => seed.deserialize(U32Deserializer::new(self.end)),
+            2 => seed.deserialize(StrDeserializer::new(self.value)),
+            _ => panic!("next_value_seed called without a matching next_key_seed"),
+        }
+    }
+}
+
+/// A `SeqAccess` over `ReservedProtocolElement`s that leaves
+/// `next_element_context_seed` at its default -- i.e. it supports spans
+/// through the reserved-name struct protocol, but not directly.
+struct PlainSeq<'de> {
+    items: std::slice::Iter<'de, &'de str>,
+}
+
+impl<'de> SeqAccess<'de> for PlainSeq<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some(item) => seed.deserialize(ReservedProtocolElement(item)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn test_spanned_vec_falls_back_without_seq_context_support() {
+    let items = ["3:7:test", "10:13:abc"];
+    let seq = PlainSeq {
+        items: items.iter(),
+    };
+    let values: Vec<Spanned<String>> = SpannedVecSeed::<String>(std::marker::PhantomData)
+        .deserialize(ReservedSeqDeserializer(seq))
+        .unwrap();
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0].inner, "test");
+    assert_eq!(values[0].span, 3..7);
+    assert_eq!(values[1].inner, "abc");
+    assert_eq!(values[1].span, 10..13);
+}
+
+/// A `Deserializer` whose only supported hint is `deserialize_seq`,
+/// handing off to the given `PlainSeq`.
+struct ReservedSeqDeserializer<'de>(PlainSeq<'de>);
+
+impl<'de> Deserializer<'de> for ReservedSeqDeserializer<'de> {
+    type Error = Error;
+
+    serde::forward_to_deserialize_any!(bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        str string bytes byte_buf option unit unit_struct newtype_struct tuple tuple_struct map
+        struct enum identifier ignored_any);
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self.0)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self.0)
+    }
+}